@@ -1,14 +1,98 @@
-use std::hash::{BuildHasher, Hash, Hasher, RandomState};
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
 
+use serde::{Deserialize, Serialize};
+
+/// A [`BuildHasher`] whose seeds are explicit `u64` keys rather than
+/// process-random state, so a [`CountMinSketch`] can be serialized and
+/// later rebuilt while still hashing every key to the same cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct SeededState {
+    key0: u64,
+    key1: u64,
+}
+
+impl SeededState {
+    fn new() -> Self {
+        SeededState {
+            key0: rand::random(),
+            key1: rand::random(),
+        }
+    }
+}
+
+impl BuildHasher for SeededState {
+    type Hasher = SeededHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        SeededHasher::new(self.key0, self.key1)
+    }
+}
+
+/// An FNV-1a style hasher seeded from two `u64` keys, so its output is
+/// reproducible across processes given the same seeds and input.
+#[derive(Debug)]
+struct SeededHasher {
+    state: u64,
+}
+
+impl SeededHasher {
+    fn new(key0: u64, key1: u64) -> Self {
+        SeededHasher {
+            state: key0 ^ key1.rotate_left(32),
+        }
+    }
+}
+
+impl Hasher for SeededHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+        for &byte in bytes {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/// An error produced while encoding or decoding a [`CountMinSketch`] to or
+/// from its on-disk byte representation.
 #[derive(Debug)]
+pub enum SerializationError {
+    Encode(bincode::Error),
+    Decode(bincode::Error),
+}
+
+impl std::fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SerializationError::Encode(err) => write!(f, "failed to encode sketch: {err}"),
+            SerializationError::Decode(err) => write!(f, "failed to decode sketch: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SerializationError::Encode(err) | SerializationError::Decode(err) => Some(err),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct CountMinSketch<K: Hash + Sync + Send + Eq> {
     width: usize,
     depth: usize,
     vec: Vec<Vec<u64>>,
-    hash_builders: Vec<RandomState>,
+    hash_builders: Vec<SeededState>,
     counter: usize,
+    #[serde(skip)]
     _phantom: PhantomData<K>,
 }
 
@@ -18,17 +102,49 @@ impl<K: Hash + Sync + Send + Eq> CountMinSketch<K> {
             width: width.into(),
             depth: depth.into(),
             vec: vec![vec![0; width.into()]; depth.into()],
-            hash_builders: (0..depth.into()).map(|_| RandomState::new()).collect(),
+            hash_builders: (0..depth.into()).map(|_| SeededState::new()).collect(),
             _phantom: PhantomData,
             counter: 0,
         }
     }
 
+    /// Builds a sketch sized from error bounds rather than raw dimensions.
+    /// After `n` insertions, `query` overshoots the true count by at most
+    /// `epsilon * n` with probability at least `1 - delta`, using the
+    /// standard Count-Min sizing `width = ceil(e / epsilon)` and
+    /// `depth = ceil(ln(1 / delta))`.
+    pub fn new_with_confidence(epsilon: f64, delta: f64) -> Self {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon must be in (0, 1), got {epsilon}"
+        );
+        assert!(
+            delta > 0.0 && delta < 1.0,
+            "delta must be in (0, 1), got {delta}"
+        );
+
+        let width = ((std::f64::consts::E / epsilon).ceil() as usize).max(1);
+        let depth = (((1.0_f64 / delta).ln().ceil()) as usize).max(1);
+
+        Self::new(
+            NonZeroUsize::new(width).unwrap(),
+            NonZeroUsize::new(depth).unwrap(),
+        )
+    }
+
+    /// The additive error guarantee implied by this sketch's width: the
+    /// amount by which a `query` result overshoots the true count at most,
+    /// with probability at least `1 - delta`. `epsilon` is recovered from
+    /// the width as `e / width`, which is at most the `epsilon` passed to
+    /// `new_with_confidence`.
+    pub fn error_bound(&self) -> f64 {
+        let epsilon = std::f64::consts::E / self.width as f64;
+        epsilon * self.total_count() as f64
+    }
+
     fn hash_with_seed(&self, key: &K, seed: usize) -> u64 {
         assert!(seed < self.depth);
-        let mut hasher = self.hash_builders[seed].build_hasher();
-        key.hash(&mut hasher);
-        hasher.finish() % self.width as u64
+        self.hash_builders[seed].hash_one(key) % self.width as u64
     }
 
     pub fn store(&mut self, key: &K) {
@@ -40,6 +156,29 @@ impl<K: Hash + Sync + Send + Eq> CountMinSketch<K> {
         }
     }
 
+    /// Inserts `key` using the conservative-update (CU) variant: instead of
+    /// incrementing every row, only cells at or below the pre-update
+    /// minimum are raised to `minimum + 1`. This keeps the no-underestimate
+    /// guarantee while substantially reducing the overestimation bias that
+    /// plain `store` accumulates from hash collisions, at the cost of no
+    /// longer supporting deletions (a cell may be shared with a key whose
+    /// count was never actually incremented here).
+    pub fn store_conservative(&mut self, key: &K) {
+        self.counter += 1;
+
+        let hashes: Vec<usize> = (0..self.depth)
+            .map(|depth_index| self.hash_with_seed(key, depth_index) as usize)
+            .collect();
+        let minimum = (0..self.depth)
+            .map(|depth_index| self.vec[depth_index][hashes[depth_index]])
+            .min()
+            .unwrap();
+
+        for (depth_index, &hash) in hashes.iter().enumerate() {
+            self.vec[depth_index][hash] = self.vec[depth_index][hash].max(minimum + 1);
+        }
+    }
+
     pub fn query(&self, key: &K) -> u64 {
         (0..self.depth)
             .map(|depth| {
@@ -57,6 +196,310 @@ impl<K: Hash + Sync + Send + Eq> CountMinSketch<K> {
     pub fn total_count(&self) -> usize {
         self.counter
     }
+
+    /// Estimates `key`'s count with the Count-Mean-Min estimator, which
+    /// debiases the systematic overestimation that plain `query` (min
+    /// across rows) accumulates from hash collisions. For each row, the
+    /// collision noise is estimated as the average of the other counters
+    /// sharing that row (`(total_count - cell) / (width - 1)`) and
+    /// subtracted from the cell; the result is the median of those
+    /// debiased residuals, clamped to `[0, query(key)]`.
+    ///
+    /// This trades away the strict never-underestimate guarantee that
+    /// `query` provides in exchange for lower average error, and is most
+    /// useful on dense sketches where `query` consistently overshoots.
+    /// Assumes every row was built with `store`, where each insertion
+    /// increments every cell in the row; `store_conservative` skips cells,
+    /// so rows no longer sum to `total_count` and the noise estimate below
+    /// no longer applies.
+    pub fn query_mean_min(&self, key: &K) -> u64 {
+        let cells: Vec<u64> = (0..self.depth)
+            .map(|depth_index| {
+                let hash = self.hash_with_seed(key, depth_index) as usize;
+                self.vec[depth_index][hash]
+            })
+            .collect();
+        let min_query = cells.iter().copied().min().unwrap();
+
+        if self.width <= 1 {
+            return min_query;
+        }
+
+        let total = self.total_count() as f64;
+        let mut residuals: Vec<f64> = cells
+            .into_iter()
+            .map(|cell| {
+                let cell = cell as f64;
+                let noise = (total - cell) / (self.width as f64 - 1.0);
+                cell - noise
+            })
+            .collect();
+        residuals.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mid = residuals.len() / 2;
+        let median = if residuals.len().is_multiple_of(2) {
+            (residuals[mid - 1] + residuals[mid]) / 2.0
+        } else {
+            residuals[mid]
+        };
+
+        median.clamp(0.0, min_query as f64).round() as u64
+    }
+
+    /// Folds `other` into `self` in place, so the result is equivalent to a
+    /// single sketch that had observed both streams. Sketches can only be
+    /// merged when their dimensions and per-row hash seeds line up, since
+    /// otherwise a cell in `other` doesn't correspond to the same hash
+    /// bucket in `self`; build compatible shards by `clone`-ing one
+    /// template sketch rather than calling `new` independently for each.
+    pub fn merge(&mut self, other: &CountMinSketch<K>) -> Result<(), MergeError> {
+        if self.width != other.width || self.depth != other.depth {
+            return Err(MergeError::DimensionMismatch {
+                self_width: self.width,
+                self_depth: self.depth,
+                other_width: other.width,
+                other_depth: other.depth,
+            });
+        }
+        if self.hash_builders != other.hash_builders {
+            return Err(MergeError::SeedMismatch);
+        }
+
+        for (row, other_row) in self.vec.iter_mut().zip(other.vec.iter()) {
+            for (cell, &other_cell) in row.iter_mut().zip(other_row.iter()) {
+                *cell = cell.saturating_add(other_cell);
+            }
+        }
+        self.counter += other.counter;
+
+        Ok(())
+    }
+}
+
+/// An error returned by [`CountMinSketch::merge`] when two sketches are not
+/// compatible to combine.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MergeError {
+    DimensionMismatch {
+        self_width: usize,
+        self_depth: usize,
+        other_width: usize,
+        other_depth: usize,
+    },
+    SeedMismatch,
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::DimensionMismatch {
+                self_width,
+                self_depth,
+                other_width,
+                other_depth,
+            } => write!(
+                f,
+                "cannot merge sketches of differing dimensions: {self_width}x{self_depth} vs {other_width}x{other_depth}"
+            ),
+            MergeError::SeedMismatch => {
+                write!(f, "cannot merge sketches built with different hash seeds")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+impl<K> CountMinSketch<K>
+where
+    K: Hash + Sync + Send + Eq + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Encodes the sketch, including its per-row hash seeds, into a
+    /// portable byte buffer that [`Self::from_bytes`] can reconstruct.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        bincode::serialize(self).map_err(SerializationError::Encode)
+    }
+
+    /// Rebuilds a sketch from bytes produced by [`Self::to_bytes`]. The
+    /// restored sketch hashes keys identically to the original, so
+    /// `query` results are preserved across the round-trip.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        bincode::deserialize(bytes).map_err(SerializationError::Decode)
+    }
+}
+
+/// Approximate frequent-item (heavy-hitters) tracking backed by a
+/// [`CountMinSketch`]. Every offered key is stored into the sketch, and the
+/// `k` keys with the highest estimated frequency seen so far are retained
+/// in a bounded min-heap.
+#[derive(Debug)]
+pub struct TopK<K: Hash + Sync + Send + Eq + Clone + Ord> {
+    sketch: CountMinSketch<K>,
+    k: usize,
+    tracked: std::collections::HashMap<K, u64>,
+    heap: std::collections::BinaryHeap<std::cmp::Reverse<(u64, K)>>,
+}
+
+impl<K: Hash + Sync + Send + Eq + Clone + Ord> TopK<K> {
+    pub fn new(width: NonZeroUsize, depth: NonZeroUsize, k: NonZeroUsize) -> Self {
+        TopK {
+            sketch: CountMinSketch::new(width, depth),
+            k: k.into(),
+            tracked: std::collections::HashMap::new(),
+            heap: std::collections::BinaryHeap::new(),
+        }
+    }
+
+    /// Once the heap holds more than this many entries per slot of `k`,
+    /// `offer` rebuilds it from `tracked` instead of relying on lazy
+    /// eviction, so a steady stream of re-offered keys can't grow the heap
+    /// without bound.
+    const HEAP_COMPACTION_FACTOR: usize = 8;
+
+    /// Stores `key` into the underlying sketch and updates the tracked set
+    /// of heavy hitters with its new estimate.
+    pub fn offer(&mut self, key: K) {
+        self.sketch.store(&key);
+        let estimate = self.sketch.query(&key);
+
+        if let Some(tracked_estimate) = self.tracked.get_mut(&key) {
+            *tracked_estimate = estimate;
+            self.heap.push(std::cmp::Reverse((estimate, key)));
+        } else if self.tracked.len() < self.k {
+            self.tracked.insert(key.clone(), estimate);
+            self.heap.push(std::cmp::Reverse((estimate, key)));
+        } else if let Some(heap_minimum) = self.current_minimum() {
+            if estimate > heap_minimum {
+                self.evict_stale_minimum();
+                self.tracked.insert(key.clone(), estimate);
+                self.heap.push(std::cmp::Reverse((estimate, key)));
+            }
+        }
+
+        self.compact_heap_if_needed();
+    }
+
+    /// Rebuilds the heap from `tracked` once it has accumulated too many
+    /// stale entries, bounding its size to a small multiple of `k`
+    /// regardless of how long the stream runs.
+    fn compact_heap_if_needed(&mut self) {
+        let threshold = self.k.max(1) * Self::HEAP_COMPACTION_FACTOR;
+        if self.heap.len() <= threshold {
+            return;
+        }
+
+        self.heap = self
+            .tracked
+            .iter()
+            .map(|(key, &estimate)| std::cmp::Reverse((estimate, key.clone())))
+            .collect();
+    }
+
+    /// Peeks the estimate of the currently tracked key with the lowest
+    /// frequency, discarding heap entries left behind by a key that was
+    /// later re-offered (its old, lower entry is still in the heap once a
+    /// newer one is pushed on update).
+    fn current_minimum(&mut self) -> Option<u64> {
+        loop {
+            let std::cmp::Reverse((estimate, key)) = self.heap.peek()?;
+            if self.tracked.get(key) == Some(estimate) {
+                return Some(*estimate);
+            }
+            self.heap.pop();
+        }
+    }
+
+    /// Pops heap entries until one is found whose estimate still matches
+    /// the tracking map (earlier entries for a re-offered key are stale),
+    /// then evicts that key from the tracked set.
+    fn evict_stale_minimum(&mut self) {
+        while let Some(std::cmp::Reverse((estimate, key))) = self.heap.pop() {
+            if self.tracked.get(&key) == Some(&estimate) {
+                self.tracked.remove(&key);
+                return;
+            }
+        }
+    }
+
+    /// Returns the tracked keys sorted by estimated frequency, highest
+    /// first.
+    pub fn top(&self) -> Vec<(K, u64)> {
+        let mut top: Vec<(K, u64)> = self
+            .tracked
+            .iter()
+            .map(|(key, &estimate)| (key.clone(), estimate))
+            .collect();
+        top.sort_by_key(|&(_, estimate)| std::cmp::Reverse(estimate));
+        top
+    }
+}
+
+/// A lock-free variant of [`CountMinSketch`] backed by atomic counters, so
+/// `store` and `query` only need a shared reference. This lets callers
+/// insert concurrently from many threads (e.g.
+/// `items.par_iter().for_each(|i| sketch.store(i))`) without wrapping the
+/// sketch in a `Mutex`, at the cost of the weaker consistency that
+/// `Ordering::Relaxed` provides between concurrent writers.
+#[derive(Debug)]
+pub struct ConcurrentCountMinSketch<K: Hash + Sync + Send + Eq> {
+    width: usize,
+    depth: usize,
+    vec: Vec<Vec<std::sync::atomic::AtomicU64>>,
+    hash_builders: Vec<SeededState>,
+    counter: std::sync::atomic::AtomicUsize,
+    _phantom: PhantomData<K>,
+}
+
+impl<K: Hash + Sync + Send + Eq> ConcurrentCountMinSketch<K> {
+    pub fn new(width: NonZeroUsize, depth: NonZeroUsize) -> Self {
+        let width: usize = width.into();
+        let depth: usize = depth.into();
+        ConcurrentCountMinSketch {
+            width,
+            depth,
+            vec: (0..depth)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| std::sync::atomic::AtomicU64::new(0))
+                        .collect()
+                })
+                .collect(),
+            hash_builders: (0..depth).map(|_| SeededState::new()).collect(),
+            counter: std::sync::atomic::AtomicUsize::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn hash_with_seed(&self, key: &K, seed: usize) -> u64 {
+        assert!(seed < self.depth);
+        self.hash_builders[seed].hash_one(key) % self.width as u64
+    }
+
+    /// Inserts `key`, using `fetch_add` with relaxed ordering so concurrent
+    /// writers only need a shared reference.
+    pub fn store(&self, key: &K) {
+        self.counter
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        for depth_index in 0..self.depth {
+            let hash = self.hash_with_seed(key, depth_index) as usize;
+            self.vec[depth_index][hash].fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// Reads the current estimate for `key` with relaxed loads.
+    pub fn query(&self, key: &K) -> u64 {
+        (0..self.depth)
+            .map(|depth_index| {
+                let hash = self.hash_with_seed(key, depth_index) as usize;
+                self.vec[depth_index][hash].load(std::sync::atomic::Ordering::Relaxed)
+            })
+            .min()
+            .unwrap()
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.counter.load(std::sync::atomic::Ordering::Relaxed)
+    }
 }
 
 #[cfg(test)]
@@ -78,7 +521,10 @@ mod proptest_tests {
             depth in 1..10usize,
             operations in prop::collection::vec(any::<String>(), 1..1000)
         ) {
-            let mut sketch = CountMinSketch::<String>::new(width, depth);
+            let mut sketch = CountMinSketch::<String>::new(
+                NonZeroUsize::new(width).unwrap(),
+                NonZeroUsize::new(depth).unwrap(),
+            );
             let mut reference_counts = std::collections::HashMap::new();
 
             // Store all operations and track in reference map
@@ -160,6 +606,60 @@ mod proptest_tests {
                 }
             }
         }
+
+        #[test]
+        fn test_conservative_update_never_underestimates(
+            width in 1..1000usize,
+            depth in 1..10usize,
+            operations in prop::collection::vec(any::<String>(), 1..1000)
+        ) {
+            let mut sketch = CountMinSketch::<String>::new(
+                NonZeroUsize::new(width).unwrap(),
+                NonZeroUsize::new(depth).unwrap(),
+            );
+            let mut reference_counts = std::collections::HashMap::new();
+
+            for key in &operations {
+                sketch.store_conservative(key);
+                *reference_counts.entry(key.clone()).or_insert(0u64) += 1;
+            }
+
+            for (key, expected_count) in reference_counts {
+                let estimated_count = sketch.query(&key);
+                assert!(
+                    estimated_count >= expected_count,
+                    "Conservative estimate ({}) should be >= actual count ({}) for key '{}'",
+                    estimated_count,
+                    expected_count,
+                    key
+                );
+            }
+        }
+
+        #[test]
+        fn test_conservative_update_is_at_most_standard(
+            width in 1..200usize,
+            depth in 1..10usize,
+            operations in prop::collection::vec(any::<String>(), 1..500)
+        ) {
+            let width = NonZeroUsize::new(width).unwrap();
+            let depth = NonZeroUsize::new(depth).unwrap();
+            let mut standard = CountMinSketch::<String>::new(width, depth);
+            let mut conservative = standard.clone();
+
+            for key in &operations {
+                standard.store(key);
+                conservative.store_conservative(key);
+            }
+
+            for key in &operations {
+                assert!(
+                    conservative.query(key) <= standard.query(key),
+                    "Conservative-update estimate should never exceed the standard estimate for key '{}'",
+                    key
+                );
+            }
+        }
     }
 }
 
@@ -256,6 +756,359 @@ mod quickcheck_tests {
     }
 }
 
+#[cfg(test)]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_preserves_queries() {
+        let mut sketch = CountMinSketch::<String>::new(
+            NonZeroUsize::new(50).unwrap(),
+            NonZeroUsize::new(4).unwrap(),
+        );
+
+        for key in ["a", "b", "a", "c", "a", "b"] {
+            sketch.store(&key.to_string());
+        }
+
+        let bytes = sketch.to_bytes().expect("serialization should succeed");
+        let restored =
+            CountMinSketch::<String>::from_bytes(&bytes).expect("deserialization should succeed");
+
+        for key in ["a", "b", "c", "missing"] {
+            let key = key.to_string();
+            assert_eq!(sketch.query(&key), restored.query(&key));
+        }
+        assert_eq!(sketch.total_count(), restored.total_count());
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_garbage() {
+        let result = CountMinSketch::<String>::from_bytes(&[0xff, 0x00, 0x01]);
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod merge_tests {
+    use super::*;
+
+    /// Mirrors the public workflow a `merge` caller would use: build one
+    /// template sketch, then clone it per shard so every shard hashes with
+    /// the same seeds.
+    fn sketch_with_same_seeds(
+        width: NonZeroUsize,
+        depth: NonZeroUsize,
+    ) -> (CountMinSketch<String>, CountMinSketch<String>) {
+        let template = CountMinSketch::<String>::new(width, depth);
+        (template.clone(), template)
+    }
+
+    #[test]
+    fn test_merge_matches_single_threaded_insert() {
+        let width = NonZeroUsize::new(64).unwrap();
+        let depth = NonZeroUsize::new(5).unwrap();
+        let (mut shard_a, mut shard_b) = sketch_with_same_seeds(width, depth);
+        let mut combined = shard_a.clone();
+
+        let stream_a = ["a", "b", "a", "c"];
+        let stream_b = ["b", "c", "c", "d"];
+
+        for key in stream_a {
+            let key = key.to_string();
+            shard_a.store(&key);
+            combined.store(&key);
+        }
+        for key in stream_b {
+            let key = key.to_string();
+            shard_b.store(&key);
+            combined.store(&key);
+        }
+
+        shard_a.merge(&shard_b).expect("compatible sketches should merge");
+
+        for key in ["a", "b", "c", "d"] {
+            let key = key.to_string();
+            assert_eq!(shard_a.query(&key), combined.query(&key));
+        }
+        assert_eq!(shard_a.total_count(), combined.total_count());
+    }
+
+    #[test]
+    fn test_merge_rejects_dimension_mismatch() {
+        let mut a = CountMinSketch::<String>::new(
+            NonZeroUsize::new(10).unwrap(),
+            NonZeroUsize::new(3).unwrap(),
+        );
+        let b = CountMinSketch::<String>::new(
+            NonZeroUsize::new(20).unwrap(),
+            NonZeroUsize::new(3).unwrap(),
+        );
+
+        assert!(matches!(
+            a.merge(&b),
+            Err(MergeError::DimensionMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_merge_rejects_seed_mismatch() {
+        let mut a = CountMinSketch::<String>::new(
+            NonZeroUsize::new(10).unwrap(),
+            NonZeroUsize::new(3).unwrap(),
+        );
+        let b = CountMinSketch::<String>::new(
+            NonZeroUsize::new(10).unwrap(),
+            NonZeroUsize::new(3).unwrap(),
+        );
+
+        assert_eq!(a.merge(&b), Err(MergeError::SeedMismatch));
+    }
+}
+
+#[cfg(test)]
+mod confidence_tests {
+    use super::*;
+
+    #[test]
+    fn test_new_with_confidence_respects_error_bound() {
+        let epsilon = 0.01;
+        let delta = 0.01;
+        let mut sketch = CountMinSketch::<u64>::new_with_confidence(epsilon, delta);
+
+        for i in 0..5000u64 {
+            sketch.store(&(i % 200));
+        }
+
+        let bound = sketch.error_bound();
+        assert!(bound > 0.0);
+        let true_count = 25u64;
+        for i in 0..200u64 {
+            let estimated = sketch.query(&i);
+            assert!(estimated >= true_count);
+            assert!((estimated - true_count) as f64 <= bound);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "epsilon must be in (0, 1)")]
+    fn test_new_with_confidence_rejects_invalid_epsilon() {
+        CountMinSketch::<u64>::new_with_confidence(0.0, 0.01);
+    }
+
+    #[test]
+    #[should_panic(expected = "delta must be in (0, 1)")]
+    fn test_new_with_confidence_rejects_invalid_delta() {
+        CountMinSketch::<u64>::new_with_confidence(0.01, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod topk_tests {
+    use super::*;
+
+    #[test]
+    fn test_top_tracks_the_most_frequent_keys() {
+        let mut topk = TopK::<String>::new(
+            NonZeroUsize::new(200).unwrap(),
+            NonZeroUsize::new(5).unwrap(),
+            NonZeroUsize::new(2).unwrap(),
+        );
+
+        for key in ["rare", "common", "common", "common", "mid", "mid"] {
+            topk.offer(key.to_string());
+        }
+
+        let top: Vec<String> = topk.top().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(top.len(), 2);
+        assert!(top.contains(&"common".to_string()));
+        assert!(top.contains(&"mid".to_string()));
+    }
+
+    #[test]
+    fn test_top_is_sorted_by_estimate_descending() {
+        let mut topk = TopK::<String>::new(
+            NonZeroUsize::new(200).unwrap(),
+            NonZeroUsize::new(5).unwrap(),
+            NonZeroUsize::new(3).unwrap(),
+        );
+
+        for (key, count) in [("a", 1), ("b", 5), ("c", 3)] {
+            for _ in 0..count {
+                topk.offer(key.to_string());
+            }
+        }
+
+        let top = topk.top();
+        let estimates: Vec<u64> = top.iter().map(|(_, estimate)| *estimate).collect();
+        let mut sorted_estimates = estimates.clone();
+        sorted_estimates.sort_by(|a, b| b.cmp(a));
+        assert_eq!(estimates, sorted_estimates);
+    }
+
+    #[test]
+    fn test_offer_does_not_evict_on_a_stale_heap_minimum() {
+        let mut topk = TopK::<String>::new(
+            NonZeroUsize::new(200).unwrap(),
+            NonZeroUsize::new(5).unwrap(),
+            NonZeroUsize::new(2).unwrap(),
+        );
+
+        for _ in 0..10 {
+            topk.offer("b".to_string());
+        }
+        for _ in 0..8 {
+            topk.offer("c".to_string());
+        }
+        for _ in 0..2 {
+            topk.offer("d".to_string());
+        }
+
+        let top: Vec<String> = topk.top().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(top.len(), 2);
+        assert!(top.contains(&"b".to_string()));
+        assert!(
+            top.contains(&"c".to_string()),
+            "the heavier key 'c' (count 8) should not be evicted in favor of \
+             the lighter key 'd' (count 2) just because the heap's top entry \
+             for 'b' was stale"
+        );
+    }
+
+    #[test]
+    fn test_heap_stays_bounded_under_a_steady_state_stream() {
+        let mut topk = TopK::<String>::new(
+            NonZeroUsize::new(200).unwrap(),
+            NonZeroUsize::new(5).unwrap(),
+            NonZeroUsize::new(2).unwrap(),
+        );
+
+        for i in 0..100_000 {
+            let key = if i % 2 == 0 { "a" } else { "b" };
+            topk.offer(key.to_string());
+        }
+
+        assert!(
+            topk.heap.len() <= 2 * TopK::<String>::HEAP_COMPACTION_FACTOR,
+            "heap should be periodically compacted instead of growing by one \
+             entry per offer, but it has {} entries",
+            topk.heap.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod concurrent_tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_concurrent_store_never_underestimates() {
+        let sketch = Arc::new(ConcurrentCountMinSketch::<u64>::new(
+            NonZeroUsize::new(256).unwrap(),
+            NonZeroUsize::new(5).unwrap(),
+        ));
+        let reference: Arc<Vec<std::sync::atomic::AtomicU64>> =
+            Arc::new((0..100).map(|_| std::sync::atomic::AtomicU64::new(0)).collect());
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let sketch = Arc::clone(&sketch);
+                let reference = Arc::clone(&reference);
+                thread::spawn(move || {
+                    for i in 0..10_000u64 {
+                        let key = i % 100;
+                        sketch.store(&key);
+                        reference[key as usize].fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for key in 0..100u64 {
+            let expected = reference[key as usize].load(std::sync::atomic::Ordering::Relaxed);
+            let estimated = sketch.query(&key);
+            assert!(
+                estimated >= expected,
+                "key {key}: estimate {estimated} should be >= true count {expected}"
+            );
+        }
+        assert_eq!(sketch.total_count(), 8 * 10_000);
+    }
+}
+
+#[cfg(test)]
+mod mean_min_tests {
+    use super::*;
+
+    /// Builds a Zipfian-distributed stream over `num_keys` ranks summing to
+    /// roughly `total_items` insertions, so low-rank keys dominate.
+    fn zipfian_stream(num_keys: usize, total_items: usize) -> Vec<u64> {
+        let harmonic: f64 = (1..=num_keys).map(|rank| 1.0 / rank as f64).sum();
+        let mut stream = Vec::with_capacity(total_items);
+        for rank in 1..=num_keys {
+            let frequency = ((1.0 / rank as f64 / harmonic) * total_items as f64).round() as usize;
+            stream.extend(std::iter::repeat(rank as u64).take(frequency));
+        }
+        stream
+    }
+
+    #[test]
+    fn test_query_mean_min_never_exceeds_min_query() {
+        let stream = zipfian_stream(50, 5000);
+        let mut sketch = CountMinSketch::<u64>::new(
+            NonZeroUsize::new(64).unwrap(),
+            NonZeroUsize::new(5).unwrap(),
+        );
+        for key in &stream {
+            sketch.store(key);
+        }
+
+        for key in 1..=50u64 {
+            assert!(sketch.query_mean_min(&key) <= sketch.query(&key));
+        }
+    }
+
+    #[test]
+    fn test_query_mean_min_has_lower_mean_absolute_error() {
+        let stream = zipfian_stream(50, 5000);
+        let mut true_counts = std::collections::HashMap::new();
+        for key in &stream {
+            *true_counts.entry(*key).or_insert(0u64) += 1;
+        }
+
+        // Dense on purpose (50 keys, width 8): collisions are frequent
+        // enough that min-query's positive bias is large, which is the
+        // regime query_mean_min is meant to help with.
+        let mut sketch = CountMinSketch::<u64>::new(
+            NonZeroUsize::new(8).unwrap(),
+            NonZeroUsize::new(5).unwrap(),
+        );
+        for key in &stream {
+            sketch.store(key);
+        }
+
+        let mut min_query_error = 0.0;
+        let mut mean_min_error = 0.0;
+        for (key, &expected) in &true_counts {
+            min_query_error += (sketch.query(key) as f64 - expected as f64).abs();
+            mean_min_error += (sketch.query_mean_min(key) as f64 - expected as f64).abs();
+        }
+        min_query_error /= true_counts.len() as f64;
+        mean_min_error /= true_counts.len() as f64;
+
+        assert!(
+            mean_min_error <= min_query_error,
+            "Count-Mean-Min MAE ({mean_min_error}) should be <= min-query MAE ({min_query_error})"
+        );
+    }
+}
+
 #[cfg(test)]
 mod stress_tests {
     use super::*;
@@ -1,59 +1,61 @@
 use rayon::prelude::*;
 use std::num::NonZeroUsize;
 
-use count_min_sketch::count_min_sketch::CountMinSketch;
+use count_min_sketch::count_min_sketch::{ConcurrentCountMinSketch, CountMinSketch};
 
 fn main() {
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use std::time::Instant;
 
     println!("Parallel Insert Strategies");
     println!("===========================\n");
 
-    let sketch = Arc::new(CountMinSketch::new(
-        NonZeroUsize::new(10000).unwrap(),
-        NonZeroUsize::new(7).unwrap(),
-    ));
-
     let items: Vec<String> = (0..100000).map(|i| format!("item_{}", i % 1000)).collect();
 
     // ============================================================
-    // METHOD 1: Parallel across multiple insert() calls (BEST!)
+    // METHOD 1: Lock-free, shared reference (BEST!)
     // ============================================================
-    println!("1. RECOMMENDED: Parallel across items");
+    println!("1. RECOMMENDED: ConcurrentCountMinSketch, no locking");
     {
-        let sketch_clone = Arc::clone(&sketch);
+        let sketch = Arc::new(ConcurrentCountMinSketch::new(
+            NonZeroUsize::new(10000).unwrap(),
+            NonZeroUsize::new(7).unwrap(),
+        ));
         let start = Instant::now();
 
-        // This parallelizes ACROSS items (good!)
+        // store(&self, ..) needs only a shared reference, so this runs
+        // without a Mutex around the sketch.
         items.par_iter().for_each(|item| {
-            sketch_clone.store_parallel(item); // Each insert is sequential internally
+            sketch.store(item);
         });
 
         let duration = start.elapsed();
         println!("   Time: {:?}", duration);
         println!(
             "   Sample: item_0 = {}",
-            sketch.count(&"item_0".to_string())
+            sketch.query(&"item_0".to_string())
         );
         println!();
     }
 
-    println!("2. Sequential across items");
+    println!("2. CountMinSketch behind a Mutex");
     {
-        let sketch_clone = Arc::clone(&sketch);
+        let sketch = Arc::new(Mutex::new(CountMinSketch::new(
+            NonZeroUsize::new(10000).unwrap(),
+            NonZeroUsize::new(7).unwrap(),
+        )));
         let start = Instant::now();
 
-        // This parallelizes ACROSS items (good!)
+        // store(&mut self, ..) forces every writer to contend for the lock.
         items.par_iter().for_each(|item| {
-            sketch_clone.store(item); // Each insert is sequential internally
+            sketch.lock().unwrap().store(item);
         });
 
         let duration = start.elapsed();
         println!("   Time: {:?}", duration);
         println!(
             "   Sample: item_0 = {}",
-            sketch.count(&"item_0".to_string())
+            sketch.lock().unwrap().query(&"item_0".to_string())
         );
         println!();
     }